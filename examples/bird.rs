@@ -16,9 +16,14 @@ fn main() {
             }),
             ..default()
         }))
-        // Add the BehaviorPlugin for Bird behavior.
-        // This plugin is required for the behavior system to work with a behavior type.
-        .add_plugins(BehaviorPlugin::<Bird>::default())
+        .add_plugins(bird_plugin)
+        .run();
+}
+
+fn bird_plugin(app: &mut App) {
+    // Add the BehaviorPlugin for Bird behavior.
+    // This plugin is required for the behavior system to work with a behavior type.
+    app.add_plugins(BehaviorPlugin::<Bird>::default())
         // Add the transition system for Bird behavior
         // Behavior changes happen in this system. Register your systems before or after it as needed.
         .add_systems(Update, transition::<Bird>)
@@ -28,8 +33,7 @@ fn main() {
             Update,
             (update_text, update_buttons).after(transition::<Bird>),
         )
-        .add_systems(Update, on_button_clicked.before(transition::<Bird>))
-        .run();
+        .add_systems(Update, on_button_clicked.before(transition::<Bird>));
 }
 
 // Define Bird behavior as an enum with all of its possible states.
@@ -219,3 +223,74 @@ fn spawn_button(parent: &mut ChildBuilder, text: impl Into<String>, action: Acti
             ));
         });
 }
+
+#[cfg(test)]
+mod tests {
+    use moonshine_behavior::Memory;
+
+    use super::*;
+
+    fn press(a: &mut App, action: fn(&Action) -> bool) {
+        let mut buttons = a.world_mut().query::<(&Action, &mut Interaction)>();
+        for (candidate, mut interaction) in buttons.iter_mut(a.world_mut()) {
+            if action(candidate) {
+                *interaction = Interaction::Pressed;
+            }
+        }
+        a.update();
+    }
+
+    fn state(a: &mut App) -> (String, Vec<String>, String) {
+        let world = a.world_mut();
+        let (bird, memory) = world.query::<(&Bird, &Memory<Bird>)>().single(world);
+        let bird = format!("{bird:?}");
+        let memory = memory.iter().map(|b| format!("{b:?}")).collect();
+        let text = world
+            .query_filtered::<&Text, With<Message>>()
+            .single(world)
+            .0
+            .clone();
+        (bird, memory, text)
+    }
+
+    #[test]
+    fn bird_example() {
+        let mut a = App::new();
+        a.add_plugins((MinimalPlugins, bird_plugin));
+        a.update();
+        assert_eq!(state(&mut a).0, "Idle");
+
+        press(&mut a, |action| matches!(action, Action::Fly));
+        assert_eq!(
+            state(&mut a),
+            ("Fly".into(), vec!["Idle".into()], "Bird is flying.".into())
+        );
+
+        press(&mut a, |action| matches!(action, Action::Chirp));
+        assert_eq!(
+            state(&mut a),
+            (
+                "Chirp".into(),
+                vec!["Fly".into(), "Idle".into()],
+                "Bird is chirping while flying.".into()
+            )
+        );
+
+        // Sleep is not allowed while chirping:
+        press(&mut a, |action| matches!(action, Action::Sleep));
+        assert_eq!(state(&mut a).0, "Chirp");
+
+        press(&mut a, |action| matches!(action, Action::Stop));
+        assert_eq!(
+            state(&mut a),
+            ("Fly".into(), vec!["Idle".into()], "Bird is flying.".into())
+        );
+
+        press(&mut a, |action| matches!(action, Action::Chirp));
+        press(&mut a, |action| matches!(action, Action::Reset));
+        assert_eq!(
+            state(&mut a),
+            ("Idle".into(), vec![], "Bird is idle.".into())
+        );
+    }
+}
@@ -65,7 +65,8 @@ fn bird_idle(time: Res<Time>, mut query: Query<(Entity, &mut Bird, &mut Controll
         let future_result = controller.try_start(Bird::Chirp);
 
         // You can either poll the result (mainly useful for diagnostics), or just forget about it!
-        // Behavior transitions only fail if `Behavior::allows_next` returns false.
+        // Behavior transitions only fail if the current behavior is terminal,
+        // or if `Behavior::allows_next` or `Behavior::allows_next_in_memory` returns false.
         future_result.forget();
 
         *elapsed = Duration::ZERO;
@@ -105,3 +106,71 @@ fn bird_fly(time: Res<Time>, mut query: Query<(Entity, &mut Bird, &mut Controlle
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Log(Vec<(Entity, &'static str)>);
+
+    fn name(bird: &Bird) -> &'static str {
+        match bird {
+            Bird::Idle { .. } => "Idle",
+            Bird::Fly { .. } => "Fly",
+            Bird::Chirp => "Chirp",
+        }
+    }
+
+    fn log(
+        mut started: Started<Bird>,
+        mut resumed: Resumed<Bird>,
+        query: Query<&Bird>,
+        mut log: ResMut<Log>,
+    ) {
+        for entity in started
+            .read()
+            .map(|event| event.entity())
+            .chain(resumed.read().map(|event| event.entity()))
+        {
+            log.0.push((entity, name(query.get(entity).unwrap())));
+        }
+    }
+
+    fn sequence(a: &App, entity: Entity) -> Vec<&'static str> {
+        let log = a.world().resource::<Log>();
+        log.0
+            .iter()
+            .filter(|(e, _)| *e == entity)
+            .map(|(_, name)| *name)
+            .collect()
+    }
+
+    #[test]
+    fn transitions_example() {
+        let mut a = App::new();
+        a.add_plugins((MinimalPlugins, bird_plugin))
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+                100,
+            )))
+            .init_resource::<Log>()
+            .add_systems(Update, log.after(transition::<Bird>));
+
+        // 6 seconds: Long enough for one chirp and one flight, but not the next chirp
+        for _ in 0..60 {
+            a.update();
+        }
+
+        let mut birds = a.world_mut().query_filtered::<Entity, With<Bird>>();
+        let mut birds: Vec<Entity> = birds.iter(a.world()).collect();
+        birds.sort();
+        let [idle, flying] = birds[..] else {
+            panic!("expected 2 birds, found {}", birds.len());
+        };
+
+        assert_eq!(sequence(&a, idle), ["Chirp", "Idle"]);
+        assert_eq!(sequence(&a, flying), ["Fly", "Idle"]);
+    }
+}
@@ -11,6 +11,7 @@ pub struct BehaviorEventWriter<'w, B: Behavior> {
     resumed: Option<ResMut<'w, Events<ResumedEvent<B>>>>,
     paused: Option<ResMut<'w, Events<PausedEvent<B>>>>,
     stopped: Option<ResMut<'w, Events<StoppedEvent<B>>>>,
+    reset: Option<ResMut<'w, Events<ResetEvent<B>>>>,
 }
 
 impl<B: Behavior> BehaviorEventWriter<'_, B> {
@@ -32,9 +33,19 @@ impl<B: Behavior> BehaviorEventWriter<'_, B> {
         }
     }
 
-    pub(crate) fn send_stopped(&mut self, instance: Instance<B>, behavior: B) {
+    pub(crate) fn send_stopped(&mut self, instance: Instance<B>, behavior: B, cause: StopCause) {
         if let Some(stopped) = &mut self.stopped {
-            stopped.send(StoppedEvent { instance, behavior });
+            stopped.send(StoppedEvent {
+                instance,
+                behavior,
+                cause,
+            });
+        }
+    }
+
+    pub(crate) fn send_reset(&mut self, instance: Instance<B>, count: usize) {
+        if let Some(reset) = &mut self.reset {
+            reset.send(ResetEvent { instance, count });
         }
     }
 }
@@ -79,10 +90,20 @@ impl<B: Behavior> PausedEvent<B> {
 }
 
 /// An event emitted when a [`Behavior`] is stopped.
+///
+/// A single transition may stop multiple behaviors (e.g. a reset).
+/// All events are sent before any of them can be read, so by the time this event is read,
+/// the current behavior and its [`Memory`](crate::Memory) reflect at least the result of the whole transition,
+/// and not the state immediately after this behavior was stopped.
+///
+/// If the reader runs after later transitions (e.g. one update later, or after another run of the
+/// [`transition`](crate::transition) system), the state also includes those transitions.
+/// Do not rely on the current state matching this event.
 #[derive(Event)]
 pub struct StoppedEvent<B: Behavior> {
     pub instance: Instance<B>,
     pub behavior: B,
+    pub cause: StopCause,
 }
 
 impl<B: Behavior> StoppedEvent<B> {
@@ -92,6 +113,39 @@ impl<B: Behavior> StoppedEvent<B> {
     }
 }
 
+/// An event emitted when a [`Behavior`] is reset.
+///
+/// This is sent once per reset, after the [`StoppedEvent`]s of all stopped behaviors, if any.
+/// See [`QuietReset`](crate::QuietReset) to only send this event instead.
+#[derive(Event)]
+pub struct ResetEvent<B: Behavior> {
+    pub instance: Instance<B>,
+    /// Number of behaviors stopped by the reset, including the one which was current.
+    pub count: usize,
+}
+
+impl<B: Behavior> ResetEvent<B> {
+    /// Returns the [`Entity`] that reset the [`Behavior`].
+    pub fn entity(&self) -> Entity {
+        self.instance.entity()
+    }
+}
+
+/// The reason a [`Behavior`] was stopped. See [`StoppedEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StopCause {
+    /// The behavior was stopped, and the previous behavior was resumed.
+    ///
+    /// This is also the cause if the stopped behavior is not [resumable](Behavior::is_resumable) and was replaced
+    /// by its [`Behavior::stopped`] successor instead. A resumable behavior replaced by its successor is paused
+    /// (see [`PausedEvent`]) and kept in [`Memory`](crate::Memory), so no [`StoppedEvent`] is sent for it.
+    Pop,
+    /// The behavior was not resumable, so it was discarded when the next behavior was started.
+    DiscardOnPush,
+    /// The behavior was stopped as part of a reset.
+    Reset,
+}
+
 /// An [`EventReader`] for [`StartedEvent`]s.
 pub type Started<'w, 's, B> = EventReader<'w, 's, StartedEvent<B>>;
 
@@ -103,3 +157,6 @@ pub type Paused<'w, 's, B> = EventReader<'w, 's, PausedEvent<B>>;
 
 /// An [`EventReader`] for [`StoppedEvent`]s.
 pub type Stopped<'w, 's, B> = EventReader<'w, 's, StoppedEvent<B>>;
+
+/// An [`EventReader`] for [`ResetEvent`]s.
+pub type Reset<'w, 's, B> = EventReader<'w, 's, ResetEvent<B>>;
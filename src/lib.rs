@@ -2,9 +2,13 @@
 
 pub mod prelude {
     pub use crate::{
-        {transition, Controller, InvalidTransition, TransitionResult}, {Behavior, BehaviorPlugin},
-        {Paused, Previous, Resumed, Started, Stopped},
-        {PausedEvent, ResumedEvent, StartedEvent, StoppedEvent},
+        {
+            run_transitions, transition, Controller, InvalidTransition, LastTransition,
+            TransitionMetrics, TransitionOutcome, TransitionRequest, TransitionRequests,
+            TransitionResult, TransitionsEnabled,
+        },
+        {Behavior, BehaviorPlugin}, {Paused, Previous, Reset, Resumed, Started, Stopped},
+        {PausedEvent, ResetEvent, ResumedEvent, StartedEvent, StopCause, StoppedEvent},
     };
 
     #[deprecated(since = "0.1.6", note = "use `Controller<B>` instead")]
@@ -13,7 +17,9 @@ pub mod prelude {
 
 mod events;
 mod memory;
+mod set;
 mod transition;
+mod validate;
 
 use std::{fmt::Debug, marker::PhantomData};
 
@@ -24,10 +30,16 @@ use moonshine_util::future::Future;
 
 pub use events::*;
 pub use memory::*;
+pub use set::*;
 pub use transition::*;
+pub use validate::*;
 
 pub struct BehaviorPlugin<B> {
     pub send_events: bool,
+    /// If set, a reset sends a single [`ResetEvent`] instead of one [`StoppedEvent`] per stopped behavior.
+    ///
+    /// See [`QuietReset`] for the tradeoff.
+    pub quiet_reset: bool,
     pub marker: PhantomData<B>,
 }
 
@@ -35,6 +47,7 @@ impl<B> Default for BehaviorPlugin<B> {
     fn default() -> Self {
         Self {
             send_events: true,
+            quiet_reset: false,
             marker: PhantomData,
         }
     }
@@ -43,12 +56,18 @@ impl<B> Default for BehaviorPlugin<B> {
 impl<B: RegisterableBehavior> Plugin for BehaviorPlugin<B> {
     fn build(&self, app: &mut App) {
         app.register_type::<Memory<B>>()
-            .register_type::<Controller<B>>();
+            .register_type::<Controller<B>>()
+            .init_resource::<TransitionsEnabled<B>>()
+            .init_resource::<TransitionRequests<B>>();
 
         if self.send_events {
             #[allow(deprecated)]
             behavior_events_plugin::<B>(app);
         }
+
+        if self.quiet_reset {
+            app.init_resource::<QuietReset<B>>();
+        }
     }
 }
 
@@ -58,7 +77,7 @@ impl<B: RegisterableBehavior> Plugin for BehaviorPlugin<B> {
 /// Add this plugin to your application during initialization for every behavior.
 ///
 /// This plugin is required for behavior transition events to be sent at runtime.
-/// Without it, the [`transition`] system will [`panic`].
+/// Without it, no events are sent and the [`transition`] system logs a warning.
 ///
 /// This plugin requires the behavior to support reflection (see [`Reflect`]).
 /// If `B` does not support reflection, use [`behavior_events_plugin`] instead.
@@ -76,7 +95,7 @@ pub fn behavior_plugin<B: RegisterableBehavior>(app: &mut App) {
 /// Add this plugin to your application during initialization for every behavior which does not support reflection.
 ///
 /// This plugin is required for behavior transition events to be sent at runtime.
-/// Without it, the [`transition`] system will [`panic`].
+/// Without it, no events are sent and the [`transition`] system logs a warning.
 ///
 /// For behaviors which do support reflection, prefer to use [`behavior_plugin`] instead.
 ///
@@ -86,7 +105,10 @@ pub fn behavior_events_plugin<B: Behavior>(app: &mut App) {
     app.add_event::<StartedEvent<B>>()
         .add_event::<PausedEvent<B>>()
         .add_event::<ResumedEvent<B>>()
-        .add_event::<StoppedEvent<B>>();
+        .add_event::<StoppedEvent<B>>()
+        .add_event::<ResetEvent<B>>()
+        .init_resource::<TransitionsEnabled<B>>()
+        .init_resource::<TransitionRequests<B>>();
 }
 
 /// A [`Component`] which represents some state of its [`Entity`].
@@ -188,11 +210,24 @@ pub fn behavior_events_plugin<B: Behavior>(app: &mut App) {
 pub trait Behavior: Component + Debug + Sized {
     /// Returns `true` if some next [`Behavior`] is allowed to be started after this one.
     ///
+    /// This is not called if this behavior is [terminal](Behavior::is_terminal), since no behavior may start after it.
+    ///
     /// By default, any behavior is allowed to start after any other behavior.
     fn allows_next(&self, _next: &Self) -> bool {
         true
     }
 
+    /// Returns `true` if some next [`Behavior`] is allowed to be started after this one,
+    /// given the [`Memory`] of paused behaviors below it.
+    ///
+    /// Override this if a transition depends on what is already paused, such as preventing the
+    /// same state from being started again while it is paused somewhere in the stack.
+    ///
+    /// By default, this delegates to [`allows_next`](Behavior::allows_next).
+    fn allows_next_in_memory(&self, next: &Self, _memory: &Memory<Self>) -> bool {
+        self.allows_next(next)
+    }
+
     /// Returns `true` if this [`Behavior`] may be resumed after it has been paused.
     ///
     /// By default, all behaviors are resumable.
@@ -200,6 +235,16 @@ pub trait Behavior: Component + Debug + Sized {
         true
     }
 
+    /// Returns `true` if this [`Behavior`] is terminal.
+    ///
+    /// A terminal behavior may not be stopped, and no other behavior may be started after it.
+    /// The only way to leave a terminal behavior is to reset.
+    ///
+    /// By default, no behavior is terminal.
+    fn is_terminal(&self) -> bool {
+        false
+    }
+
     /// This method is called when the current [`Behavior`] is started.
     ///
     /// By default, it does nothing.
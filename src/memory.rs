@@ -26,6 +26,14 @@ impl<B: Behavior> Memory<B> {
         self.0.iter().rev()
     }
 
+    /// Returns the paused [`Behavior`] states as a slice.
+    ///
+    /// Unlike [`iter`](Memory::iter), the slice is ordered from the oldest paused state (initial) to the most recent one (previous).
+    /// The current behavior is not included.
+    pub fn as_slice(&self) -> &[B] {
+        &self.0
+    }
+
     /// Returns `true` if the stack contains the given [`Behavior`] state.
     pub fn contains(&self, behavior: &B) -> bool
     where
@@ -54,13 +62,28 @@ impl<B: Behavior> Default for Memory<B> {
     }
 }
 
+/// A [`QueryData`] which only fetches the [`Memory`] of a [`Behavior`].
+///
+/// This does not access the current behavior or its [`Controller`](crate::Controller),
+/// so it may be used alongside systems which mutate them.
 #[derive(QueryData)]
 pub struct Previous<B: Behavior> {
     memory: &'static Memory<B>,
 }
 
-impl<B: Behavior> PreviousItem<'_, B> {
+impl<'w, B: Behavior> PreviousItem<'w, B> {
+    /// Returns a reference to the previous [`Behavior`] state, if it exists.
     pub fn get(&self) -> Option<&B> {
         self.memory.previous()
     }
+
+    /// Returns the number of paused [`Behavior`] states below the current one.
+    pub fn depth(&self) -> usize {
+        self.memory.len()
+    }
+
+    /// Returns the [`Memory`] of the [`Behavior`].
+    pub fn memory(&self) -> &'w Memory<B> {
+        self.memory
+    }
 }
@@ -0,0 +1,103 @@
+use bevy_ecs::prelude::*;
+use bevy_utils::tracing::warn;
+
+use moonshine_kind::prelude::*;
+
+use crate::{Behavior, Controller};
+
+/// Adds a [`BehaviorPlugin`](crate::BehaviorPlugin) and a [`transition`](crate::transition) system
+/// to the given schedule for each of the given [`Behavior`] types.
+///
+/// The first argument must be a `&mut App`.
+/// The [`transition`](crate::transition) systems are not ordered relative to each other.
+/// If a system reacts to one behavior by requesting a transition on another, add the systems yourself and order them explicitly.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use moonshine_behavior::{prelude::*, add_behaviors};
+///
+/// #[derive(Component, Default, Debug, Reflect)]
+/// #[reflect(Component)]
+/// enum Bird { #[default] Idle, Fly }
+///
+/// impl Behavior for Bird {}
+///
+/// #[derive(Component, Default, Debug, Reflect)]
+/// #[reflect(Component)]
+/// enum Wings { #[default] Folded, Flap }
+///
+/// impl Behavior for Wings {}
+///
+/// let mut app = App::new();
+/// add_behaviors!(&mut app, Update, Bird, Wings);
+/// ```
+#[macro_export]
+macro_rules! add_behaviors {
+    ($app:expr, $schedule:expr, $($behavior:ty),+ $(,)?) => {{
+        let app = $app;
+        $(
+            app.add_plugins($crate::BehaviorPlugin::<$behavior>::default())
+                .add_systems($schedule, $crate::transition::<$behavior>);
+        )+
+    }};
+}
+
+/// A tuple of [`Behavior`] types which may be controlled together, such as with [`reset_all`].
+pub trait BehaviorSet: 'static {
+    /// Calls [`Controller::reset`] on the given entity for each [`Behavior`] in this set.
+    fn reset(entity: &mut EntityWorldMut);
+}
+
+macro_rules! impl_behavior_set {
+    ($($behavior:ident),+) => {
+        impl<$($behavior: Behavior),+> BehaviorSet for ($($behavior,)+) {
+            fn reset(entity: &mut EntityWorldMut) {
+                $(reset::<$behavior>(entity);)+
+            }
+        }
+    };
+}
+
+impl_behavior_set!(B0);
+impl_behavior_set!(B0, B1);
+impl_behavior_set!(B0, B1, B2);
+impl_behavior_set!(B0, B1, B2, B3);
+impl_behavior_set!(B0, B1, B2, B3, B4);
+impl_behavior_set!(B0, B1, B2, B3, B4, B5);
+impl_behavior_set!(B0, B1, B2, B3, B4, B5, B6);
+impl_behavior_set!(B0, B1, B2, B3, B4, B5, B6, B7);
+
+/// An [`EntityCommand`] which resets every [`Behavior`] in the given [`BehaviorSet`] on an entity.
+///
+/// Behaviors which are not on the entity are ignored with a warning.
+///
+/// # Example
+/// ```
+/// # use bevy::prelude::*;
+/// # use moonshine_behavior::{prelude::*, reset_all};
+/// # #[derive(Component, Default, Debug, Reflect)]
+/// # enum Bird { #[default] Idle, Fly }
+/// # impl Behavior for Bird {}
+/// # #[derive(Component, Default, Debug, Reflect)]
+/// # enum Wings { #[default] Folded, Flap }
+/// # impl Behavior for Wings {}
+/// fn land(In(entity): In<Entity>, mut commands: Commands) {
+///     commands.entity(entity).queue(reset_all::<(Bird, Wings)>);
+/// }
+/// ```
+pub fn reset_all<T: BehaviorSet>(mut entity: EntityWorldMut) {
+    T::reset(&mut entity);
+}
+
+fn reset<B: Behavior>(entity: &mut EntityWorldMut) {
+    if let Some(mut controller) = entity.get_mut::<Controller<B>>() {
+        controller.reset();
+    } else {
+        warn!(
+            "{}: reset is ignored; entity has no {}",
+            entity.id(),
+            B::debug_name()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use std::{fmt, mem};
+use std::{fmt, marker::PhantomData, mem};
 
 use bevy_ecs::prelude::*;
 use bevy_reflect::prelude::*;
@@ -7,7 +7,7 @@ use bevy_utils::tracing::{debug, error, warn};
 use moonshine_kind::{prelude::*, InstanceMutItem};
 use moonshine_util::future::{Future, Promise};
 
-use crate::{Behavior, BehaviorEventWriter, Memory};
+use crate::{Behavior, BehaviorEventWriter, Memory, StopCause};
 
 use Transition::*;
 
@@ -69,7 +69,15 @@ impl<B: Behavior> Controller<B> {
     }
 
     pub fn is_suspending(&self) -> bool {
-        matches!(self.transition, Next(..) | Previous | Reset)
+        matches!(self.transition, Next(..) | Previous | PreviousN(..) | Reset)
+    }
+
+    /// Returns `true` if a transition to a [`Behavior`] which matches the given predicate is pending.
+    pub fn is_transitioning_to(&self, predicate: impl FnOnce(&B) -> bool) -> bool {
+        match &self.transition {
+            Next(next, ..) => predicate(next),
+            _ => false,
+        }
     }
 
     pub fn try_start(&mut self, behavior: B) -> Future<TransitionResult<B>> {
@@ -90,6 +98,26 @@ impl<B: Behavior> Controller<B> {
         }
     }
 
+    /// Stops the current [`Behavior`] and the `n - 1` paused behaviors below it, and resumes the one below them.
+    ///
+    /// If `n` exceeds the number of paused behaviors, this stops all of them and resumes the initial behavior.
+    /// Each stopped behavior sends a [`StoppedEvent`](crate::StoppedEvent) with [`StopCause::Pop`],
+    /// and only the resumed behavior sends a [`ResumedEvent`](crate::ResumedEvent).
+    ///
+    /// Unlike [`stop`](Self::stop), this does not start any [`Behavior::stopped`] successor.
+    /// If `n` is zero, this does nothing.
+    pub fn stop_n(&mut self, n: usize) {
+        if n == 0 {
+            return;
+        }
+        let old = Self {
+            transition: mem::replace(&mut self.transition, PreviousN(n)),
+        };
+        if old.is_suspending() {
+            warn!("transition override: {old:?} -> {self:?}");
+        }
+    }
+
     pub fn reset(&mut self) {
         let old = Self {
             transition: mem::replace(&mut self.transition, Reset),
@@ -117,6 +145,7 @@ impl<B: Behavior> Controller<B> {
                 Resumed => Resumed,
                 Next(next, ..) => Next(next.clone(), Promise::new()),
                 Previous => Previous,
+                PreviousN(n) => PreviousN(*n),
                 Reset => Reset,
             },
         }
@@ -135,6 +164,10 @@ impl<B: Behavior> fmt::Debug for Controller<B> {
                 .field(next)
                 .finish(),
             Previous => write!(f, "Transition::<{}>::Previous", B::debug_name()),
+            PreviousN(n) => f
+                .debug_tuple(format!("Transition::<{}>::PreviousN", B::debug_name()).as_str())
+                .field(n)
+                .finish(),
             Reset => write!(f, "Transition::<{}>::Reset", B::debug_name()),
         }
     }
@@ -145,54 +178,420 @@ pub type TransitionResult<B> = Result<(), InvalidTransition<B>>;
 #[derive(Debug)]
 pub struct InvalidTransition<B: Behavior>(pub B);
 
+impl<B: Behavior> fmt::Display for InvalidTransition<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "transition to {:?} is not allowed", self.0)
+    }
+}
+
+impl<B: Behavior> std::error::Error for InvalidTransition<B> {}
+
 /// A [`System`] which triggers [`Behavior`] transitions.
+///
+/// # Timing
+///
+/// Requests made through a [`Controller`] (or [`TransitionRequests`]) are invoked on the next run of this system.
+/// A request made by a system which runs before this system is invoked within the same update.
+/// A request made after this system has run waits until the next update.
+/// Order your systems relative to this system if this matters.
+///
+/// # Event Order
+///
+/// Events are sent in place while each entity is processed, so all events of one entity are
+/// sent together and in the order their transition steps happened.
+///
+/// Across entities, events follow the query iteration order. This order is not sorted by
+/// [`Entity`] and may change as entities are spawned, despawned, or moved between archetypes.
+/// If you need a stable global order (e.g. for replays), sort the events by [`Entity`] when reading them.
+///
+/// # Diagnostics
+///
+/// A warning is logged once if the [`Behavior`] is not registered with a [`BehaviorPlugin`](crate::BehaviorPlugin).
 pub fn transition<B: Behavior>(
-    mut query: Query<(InstanceMut<B>, &mut Memory<B>, &mut Controller<B>)>,
+    mut query: Query<(
+        InstanceMut<B>,
+        &mut Memory<B>,
+        &mut Controller<B>,
+        Option<&mut LastTransition<B>>,
+    )>,
     mut events: BehaviorEventWriter<B>,
+    enabled: Option<Res<TransitionsEnabled<B>>>,
+    requests: Option<ResMut<TransitionRequests<B>>>,
+    mut metrics: Option<ResMut<TransitionMetrics<B>>>,
+    quiet: Option<Res<QuietReset<B>>>,
+    mut checked: Local<bool>,
 ) {
-    for (mut current, memory, mut transition) in &mut query {
-        use Transition::*;
+    if !*checked {
+        *checked = true;
+        // Both `BehaviorPlugin` and `behavior_events_plugin` insert this resource
+        if requests.is_none() {
+            let name = B::debug_name();
+            warn!("{name} is not registered; did you forget to add BehaviorPlugin::<{name}>?");
+        }
+    }
+
+    let enabled = enabled.is_none_or(|enabled| enabled.is_enabled());
 
-        if transition.is_stable() {
-            // Do not mutate the transition if stable
-            continue;
+    // Only access the requests mutably if there are any, so they are not marked as changed on every run
+    if let Some(mut requests) = requests.filter(|requests| enabled && !requests.is_empty()) {
+        for (entity, request) in requests.requests.drain(..) {
+            let Ok((_, _, mut controller, _)) = query.get_mut(entity) else {
+                warn!(
+                    "{entity}: {request:?} is ignored; entity has no {}",
+                    B::debug_name()
+                );
+                continue;
+            };
+            match request {
+                TransitionRequest::Start(next) => controller.try_start(next).forget(),
+                TransitionRequest::Stop => controller.stop(),
+                TransitionRequest::Reset => controller.reset(),
+            }
         }
+    }
 
-        match transition.take().transition {
-            Next(next, promise) => {
-                let result = push(&mut current, next, memory, &mut events);
-                if result.is_ok() {
-                    if let Some(next) = current.started() {
-                        transition.transition = Next(next, Promise::new());
+    let mut count = |f: fn(&mut TransitionMetrics<B>)| {
+        if let Some(metrics) = &mut metrics {
+            f(metrics);
+        }
+    };
+
+    for (mut current, mut memory, mut transition, last) in &mut query {
+        use Transition::*;
+
+        let outcome = if transition.is_stable() || (!enabled && transition.is_suspending()) {
+            // Do not mutate the transition if stable, and defer it until transitions are enabled again
+            TransitionOutcome::None
+        } else {
+            let from = memory.len();
+            match transition.take().transition {
+                Next(next, promise) => {
+                    let result = push(
+                        &mut current,
+                        next,
+                        &mut memory,
+                        &mut events,
+                        StopCause::DiscardOnPush,
+                    );
+                    let outcome = if result.is_ok() {
+                        count(|metrics| metrics.pushes += 1);
+                        if let Some(next) = current.started() {
+                            transition.transition = Next(next, Promise::new());
+                        } else {
+                            transition.transition = Started;
+                        }
+                        TransitionOutcome::Started(memory.len())
+                    } else {
+                        count(|metrics| metrics.rejections += 1);
+                        TransitionOutcome::Rejected
+                    };
+                    promise.set(result);
+                    outcome
+                }
+                Previous => {
+                    if current.is_terminal() {
+                        warn!(
+                            "{current:?}: {:?} is terminal; -> None is not allowed",
+                            **current
+                        );
+                        count(|metrics| metrics.rejections += 1);
+                        TransitionOutcome::Rejected
+                    } else if let Some(next) = current.stopped() {
+                        // The current behavior is stopped, even if it is replaced by its successor
+                        let value =
+                            push(&mut current, next, &mut memory, &mut events, StopCause::Pop);
+                        if value.is_ok() {
+                            count(|metrics| metrics.pushes += 1);
+                            transition.transition = Started;
+                            TransitionOutcome::Replaced(memory.len())
+                        } else {
+                            count(|metrics| metrics.rejections += 1);
+                            TransitionOutcome::Rejected
+                        }
+                    } else if pop(&mut current, &mut memory, &mut events) {
+                        count(|metrics| metrics.pops += 1);
+                        transition.transition = Resumed;
+                        TransitionOutcome::Stopped {
+                            from,
+                            to: memory.len(),
+                        }
                     } else {
-                        transition.transition = Started;
+                        count(|metrics| metrics.rejections += 1);
+                        TransitionOutcome::Rejected
                     }
                 }
-                promise.set(result);
-            }
-            Previous => {
-                if let Some(next) = current.stopped() {
-                    let value = push(&mut current, next, memory, &mut events);
-                    if value.is_ok() {
-                        transition.transition = Started;
+                PreviousN(n) => {
+                    if current.is_terminal() {
+                        warn!(
+                            "{current:?}: {:?} is terminal; -> None is not allowed",
+                            **current
+                        );
+                        count(|metrics| metrics.rejections += 1);
+                        TransitionOutcome::Rejected
+                    } else if pop_n(&mut current, &mut memory, &mut events, n) {
+                        count(|metrics| metrics.pops += 1);
+                        transition.transition = Resumed;
+                        TransitionOutcome::Stopped {
+                            from,
+                            to: memory.len(),
+                        }
+                    } else {
+                        count(|metrics| metrics.rejections += 1);
+                        TransitionOutcome::Rejected
                     }
-                } else if pop(&mut current, memory, &mut events) {
-                    transition.transition = Resumed;
                 }
-            }
-            Reset => {
-                if reset(&mut current, memory, &mut events) {
-                    transition.transition = Resumed;
+                Reset => {
+                    if reset(&mut current, &mut memory, &mut events, quiet.is_some()) {
+                        count(|metrics| metrics.resets += 1);
+                        transition.transition = Resumed;
+                        TransitionOutcome::Reset { from }
+                    } else {
+                        count(|metrics| metrics.rejections += 1);
+                        TransitionOutcome::Rejected
+                    }
                 }
+                Started | Resumed => {
+                    transition.transition = Stable;
+                    TransitionOutcome::None
+                }
+                Stable => unreachable!(),
             }
-            Started | Resumed => {
-                transition.transition = Stable;
+        };
+
+        if let Some(mut last) = last {
+            // Avoid marking the component as changed if nothing happened again
+            if last.outcome != outcome {
+                last.outcome = outcome;
             }
-            Stable => unreachable!(),
         }
     }
 }
 
+/// The outcome of the last run of the [`transition`] system for an entity. See [`LastTransition`].
+///
+/// Indices refer to positions in the behavior stack, where the initial behavior is at index 0.
+/// The current behavior is at index [`Memory::len`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TransitionOutcome {
+    /// No transition was invoked.
+    #[default]
+    None,
+    /// A behavior was started at the given index.
+    Started(usize),
+    /// The behavior at index `from` was stopped, and the one at index `to` was resumed.
+    ///
+    /// This is the outcome of [`Controller::stop`] and [`Controller::stop_n`].
+    Stopped { from: usize, to: usize },
+    /// The current behavior was stopped, and its [`Behavior::stopped`] successor was started at the given index.
+    Replaced(usize),
+    /// The behavior at index `from` and all behaviors below it were stopped, and the initial behavior was resumed.
+    Reset { from: usize },
+    /// The requested transition was not allowed or had no effect (such as a redundant reset).
+    Rejected,
+}
+
+/// A [`Component`] which stores the [`TransitionOutcome`] of the last run of the [`transition`] system.
+///
+/// This is optional. Insert it on entities which need it, and read it in systems which run after the [`transition`] system.
+/// Unlike [`Controller::is_started`] or [`Controller::is_resumed`], this also tells rejected transitions
+/// apart from updates where nothing happened, and a [`Behavior::stopped`] successor apart from a started behavior.
+///
+/// The outcome is updated on every run of the [`transition`] system, so it is only visible until the next run.
+#[derive(Component, Debug)]
+pub struct LastTransition<B: Behavior> {
+    outcome: TransitionOutcome,
+    marker: PhantomData<B>,
+}
+
+impl<B: Behavior> LastTransition<B> {
+    pub fn outcome(&self) -> TransitionOutcome {
+        self.outcome
+    }
+}
+
+impl<B: Behavior> Default for LastTransition<B> {
+    fn default() -> Self {
+        Self {
+            outcome: TransitionOutcome::None,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// A [`Resource`] which enables or disables all transitions of a [`Behavior`].
+///
+/// While disabled, the [`transition`] system only settles started or resumed behaviors (see [`Controller::is_activated`]).
+/// Any requested transitions remain pending and are invoked once transitions are enabled again.
+///
+/// This resource is inserted by [`BehaviorPlugin`](crate::BehaviorPlugin). If it is missing, transitions are enabled.
+#[derive(Resource)]
+pub struct TransitionsEnabled<B: Behavior> {
+    enabled: bool,
+    marker: PhantomData<B>,
+}
+
+impl<B: Behavior> TransitionsEnabled<B> {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    pub fn disable(&mut self) {
+        self.enabled = false;
+    }
+}
+
+impl<B: Behavior> Default for TransitionsEnabled<B> {
+    fn default() -> Self {
+        Self::new(true)
+    }
+}
+
+/// A [`Resource`] which makes a reset of a [`Behavior`] send a single [`ResetEvent`](crate::ResetEvent) instead of one [`StoppedEvent`](crate::StoppedEvent) per stopped behavior.
+///
+/// This resource is inserted by [`BehaviorPlugin`](crate::BehaviorPlugin) if [`quiet_reset`](crate::BehaviorPlugin::quiet_reset) is set.
+///
+/// This avoids a flood of events when resetting a deep stack. However, the stopped behaviors are dropped without
+/// being sent to anyone, so any system which relies on a [`StoppedEvent`](crate::StoppedEvent) for cleanup (e.g. despawning an effect
+/// when a behavior stops) must also handle [`ResetEvent`](crate::ResetEvent) if this is used.
+/// The [`ResumedEvent`](crate::ResumedEvent) of the initial behavior is still sent.
+#[derive(Resource)]
+pub struct QuietReset<B: Behavior>(PhantomData<B>);
+
+impl<B: Behavior> Default for QuietReset<B> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// A transition request which may be sent to an entity through [`TransitionRequests`].
+#[derive(Debug, Clone)]
+pub enum TransitionRequest<B: Behavior> {
+    /// See [`Controller::try_start`].
+    Start(B),
+    /// See [`Controller::stop`].
+    Stop,
+    /// See [`Controller::reset`].
+    Reset,
+}
+
+/// A [`Resource`] which collects [`TransitionRequest`]s for entities from outside of their systems.
+///
+/// This is useful for request producers which do not have access to the [`Controller`] of an entity,
+/// such as scripts or network code.
+///
+/// All requests are applied to the [`Controller`] of their entities at the start of the next [`transition`] run,
+/// in the order they were pushed. Like any other request, only the last one per entity is invoked.
+///
+/// This resource is inserted by [`BehaviorPlugin`](crate::BehaviorPlugin). If it is missing, the [`transition`]
+/// system assumes the [`Behavior`] is not registered.
+#[derive(Resource)]
+pub struct TransitionRequests<B: Behavior> {
+    requests: Vec<(Entity, TransitionRequest<B>)>,
+}
+
+impl<B: Behavior> TransitionRequests<B> {
+    /// Pushes a new [`TransitionRequest`] for the given [`Entity`].
+    pub fn push(&mut self, entity: Entity, request: TransitionRequest<B>) {
+        self.requests.push((entity, request));
+    }
+
+    /// Pushes the same [`TransitionRequest`] for all given entities.
+    pub fn push_many(
+        &mut self,
+        entities: impl IntoIterator<Item = Entity>,
+        request: TransitionRequest<B>,
+    ) where
+        B: Clone,
+    {
+        self.requests
+            .extend(entities.into_iter().map(|entity| (entity, request.clone())));
+    }
+
+    /// Returns the number of pending requests.
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Returns `true` if there are no pending requests.
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+}
+
+impl<B: Behavior> Default for TransitionRequests<B> {
+    fn default() -> Self {
+        Self {
+            requests: Vec::new(),
+        }
+    }
+}
+
+/// A [`Resource`] which counts the transitions invoked by the [`transition`] system for a [`Behavior`].
+///
+/// This resource is optional. Insert it to opt into counting (e.g. `app.init_resource::<TransitionMetrics<B>>()`).
+/// Counts accumulate across updates until [`clear`](TransitionMetrics::clear) is called,
+/// so call it once per frame after reading for per-frame metrics.
+#[derive(Resource, Debug)]
+pub struct TransitionMetrics<B: Behavior> {
+    /// Number of behaviors started.
+    pub pushes: usize,
+    /// Number of behaviors stopped and resumed to the previous one.
+    pub pops: usize,
+    /// Number of resets.
+    pub resets: usize,
+    /// Number of transitions which were not allowed or had no effect (such as a redundant reset).
+    pub rejections: usize,
+    marker: PhantomData<B>,
+}
+
+impl<B: Behavior> TransitionMetrics<B> {
+    /// Returns the total number of transitions counted, including rejected ones.
+    pub fn total(&self) -> usize {
+        self.pushes + self.pops + self.resets + self.rejections
+    }
+
+    /// Resets all counts to zero.
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+}
+
+impl<B: Behavior> Default for TransitionMetrics<B> {
+    fn default() -> Self {
+        Self {
+            pushes: 0,
+            pops: 0,
+            resets: 0,
+            rejections: 0,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Runs a single pass of the [`transition`] system for the given [`Behavior`] directly on a [`World`].
+///
+/// This is useful outside of a schedule, such as in tests or when stepping a simulation manually.
+/// Each call invokes at most one transition per entity, same as a single run of the [`transition`] system.
+///
+/// The system is cached within the [`World`], so its state (such as the [diagnostics](transition#diagnostics))
+/// persists between calls.
+pub fn run_transitions<B: Behavior>(world: &mut World) {
+    world
+        .run_system_cached(transition::<B>)
+        .expect("transition system should be valid");
+}
+
 #[derive(Component, Default, Reflect)]
 #[reflect(Component)]
 enum Transition<B: Behavior> {
@@ -205,16 +604,25 @@ enum Transition<B: Behavior> {
     #[reflect(ignore)]
     Previous,
     #[reflect(ignore)]
+    PreviousN(usize),
+    #[reflect(ignore)]
     Reset,
 }
 
 fn push<B: Behavior>(
     current: &mut InstanceMutItem<B>,
     mut next: B,
-    mut memory: Mut<Memory<B>>,
+    memory: &mut Mut<Memory<B>>,
     events: &mut BehaviorEventWriter<B>,
+    cause: StopCause,
 ) -> TransitionResult<B> {
-    if current.allows_next(&next) {
+    if current.is_terminal() {
+        warn!(
+            "{current:?}: {:?} is terminal; -> {next:?} is not allowed",
+            **current
+        );
+        Err(InvalidTransition(next))
+    } else if current.allows_next_in_memory(&next, &memory) {
         debug!("{current:?}: {:?} -> {next:?}", **current);
         let behavior = {
             mem::swap(current.as_mut(), &mut next);
@@ -224,7 +632,7 @@ fn push<B: Behavior>(
             events.send_paused(current.instance());
             memory.push(behavior);
         } else {
-            events.send_stopped(current.instance(), behavior);
+            events.send_stopped(current.instance(), behavior, cause);
         }
         events.send_started(current.instance());
         Ok(())
@@ -236,7 +644,7 @@ fn push<B: Behavior>(
 
 fn pop<B: Behavior>(
     current: &mut InstanceMutItem<B>,
-    mut memory: Mut<Memory<B>>,
+    memory: &mut Mut<Memory<B>>,
     events: &mut BehaviorEventWriter<B>,
 ) -> bool {
     if let Some(mut next) = memory.pop() {
@@ -246,7 +654,7 @@ fn pop<B: Behavior>(
             next
         };
         events.send_resumed(current.instance());
-        events.send_stopped(current.instance(), behavior);
+        events.send_stopped(current.instance(), behavior, StopCause::Pop);
         true
     } else {
         error!("{current:?}: {:?} -> None is not allowed", **current);
@@ -254,14 +662,47 @@ fn pop<B: Behavior>(
     }
 }
 
+fn pop_n<B: Behavior>(
+    current: &mut InstanceMutItem<B>,
+    memory: &mut Mut<Memory<B>>,
+    events: &mut BehaviorEventWriter<B>,
+    n: usize,
+) -> bool {
+    let n = n.min(memory.len());
+    if n == 0 {
+        error!("{current:?}: {:?} -> None is not allowed", **current);
+        return false;
+    }
+
+    for _ in 1..n {
+        let behavior = memory.pop().unwrap();
+        events.send_stopped(current.instance(), behavior, StopCause::Pop);
+    }
+
+    let mut next = memory.pop().unwrap();
+    debug!("{current:?}: {:?} -> {next:?}", **current);
+    let behavior = {
+        mem::swap(current.as_mut(), &mut next);
+        next
+    };
+    events.send_resumed(current.instance());
+    events.send_stopped(current.instance(), behavior, StopCause::Pop);
+    true
+}
+
 fn reset<B: Behavior>(
     current: &mut InstanceMutItem<B>,
-    mut memory: Mut<Memory<B>>,
+    memory: &mut Mut<Memory<B>>,
     events: &mut BehaviorEventWriter<B>,
+    quiet: bool,
 ) -> bool {
+    let count = memory.len();
+
     while memory.len() > 1 {
         let behavior = memory.pop().unwrap();
-        events.send_stopped(current.instance(), behavior);
+        if !quiet {
+            events.send_stopped(current.instance(), behavior, StopCause::Reset);
+        }
     }
 
     if let Some(mut next) = memory.pop() {
@@ -271,7 +712,10 @@ fn reset<B: Behavior>(
             next
         };
         events.send_resumed(current.instance());
-        events.send_stopped(current.instance(), behavior);
+        if !quiet {
+            events.send_stopped(current.instance(), behavior, StopCause::Reset);
+        }
+        events.send_reset(current.instance(), count);
         true
     } else {
         warn!(
@@ -0,0 +1,92 @@
+use std::collections::VecDeque;
+
+use crate::Behavior;
+
+/// The result of [`validate_transitions`].
+///
+/// All states are referred to by their index in the slice given to [`validate_transitions`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransitionReport {
+    /// States which may never be started from the initial state.
+    pub unreachable: Vec<usize>,
+    /// States which do not allow any next state, including all [terminal](Behavior::is_terminal) states.
+    ///
+    /// Non-terminal sinks may only be left by stopping them (or by a reset).
+    /// Terminal states may only be left by a reset.
+    pub sinks: Vec<usize>,
+}
+
+impl TransitionReport {
+    /// Returns `true` if every state is reachable from the initial state.
+    ///
+    /// Sink states are not considered invalid, since they are often intentional.
+    pub fn is_valid(&self) -> bool {
+        self.unreachable.is_empty()
+    }
+}
+
+/// Probes [`Behavior::allows_next`] for every pair of the given states and returns a [`TransitionReport`].
+///
+/// The first state is treated as the initial state.
+/// [Terminal](Behavior::is_terminal) states allow no next state, regardless of [`Behavior::allows_next`].
+///
+/// This is intended to be used in unit tests to catch states which can never be started,
+/// usually because [`Behavior::allows_next`] was not updated after adding a new state.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::*;
+/// use moonshine_behavior::{prelude::*, validate_transitions};
+///
+/// #[derive(Component, Debug)]
+/// enum Bird {
+///     Idle,
+///     Fly,
+///     Chirp,
+/// }
+///
+/// impl Behavior for Bird {
+///     fn allows_next(&self, next: &Self) -> bool {
+///         use Bird::*;
+///         matches!((self, next), (Idle, Fly) | (Fly, Chirp))
+///     }
+/// }
+///
+/// let report = validate_transitions(&[Bird::Idle, Bird::Fly, Bird::Chirp]);
+/// assert!(report.is_valid());
+/// assert_eq!(report.sinks, vec![2]);
+/// ```
+///
+/// # Panics
+///
+/// If `states` is empty.
+pub fn validate_transitions<B: Behavior>(states: &[B]) -> TransitionReport {
+    assert!(!states.is_empty(), "no states to validate");
+
+    let mut reachable = vec![false; states.len()];
+    let mut pending = VecDeque::from([0]);
+    reachable[0] = true;
+    while let Some(current) = pending.pop_front() {
+        if states[current].is_terminal() {
+            continue;
+        }
+        for (index, next) in states.iter().enumerate() {
+            if !reachable[index] && states[current].allows_next(next) {
+                reachable[index] = true;
+                pending.push_back(index);
+            }
+        }
+    }
+
+    let unreachable = (0..states.len()).filter(|&i| !reachable[i]).collect();
+    let sinks = states
+        .iter()
+        .enumerate()
+        .filter(|(_, current)| {
+            current.is_terminal() || !states.iter().any(|next| current.allows_next(next))
+        })
+        .map(|(index, _)| index)
+        .collect();
+
+    TransitionReport { unreachable, sinks }
+}
@@ -0,0 +1,27 @@
+//! Fixtures shared by integration tests.
+
+// Each test crate only uses some of these:
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+
+use moonshine_behavior::{prelude::*, RegisterableBehavior};
+
+/// Returns an [`App`] with the given [`Behavior`] registered and its [`transition`] system in [`Update`].
+pub fn app<B: RegisterableBehavior>() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, BehaviorPlugin::<B>::default()))
+        .add_systems(Update, transition::<B>);
+    app
+}
+
+pub fn controller<B: Behavior>(a: &mut App, e: Entity) -> Mut<'_, Controller<B>> {
+    a.world_mut().get_mut::<Controller<B>>(e).unwrap()
+}
+
+/// Requests a transition to `next`, runs an update, and returns the result.
+pub fn start<B: Behavior>(a: &mut App, e: Entity, next: B) -> TransitionResult<B> {
+    let r = controller(a, e).try_start(next);
+    a.update();
+    r.poll().unwrap()
+}
@@ -0,0 +1,96 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+use moonshine_behavior::prelude::*;
+
+mod common;
+
+use common::app;
+
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+enum B {
+    #[default]
+    S0,
+    S1,
+    S2,
+    S3,
+}
+
+use B::*;
+
+impl Behavior for B {
+    fn allows_next(&self, next: &Self) -> bool {
+        matches!((self, next), (S0, S1) | (S1, S2) | (S0, S3) | (S3, S2))
+    }
+
+    fn is_resumable(&self) -> bool {
+        !matches!(self, S1 | S3)
+    }
+
+    fn stopped(&self) -> Option<Self> {
+        match self {
+            S3 => Some(S2),
+            _ => None,
+        }
+    }
+}
+
+fn stopped(a: &mut App) -> Vec<(B, StopCause)> {
+    a.world_mut()
+        .run_system_once(|mut events: Stopped<B>| -> Vec<(B, StopCause)> {
+            events
+                .read()
+                .map(|event| (event.behavior, event.cause))
+                .collect()
+        })
+        .unwrap()
+}
+
+#[test]
+fn stop_cause_pop() {
+    let mut a = app::<B>();
+    a.world_mut().spawn((S0, Controller::next(S1)));
+    a.update();
+    a.world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| q.single_mut().stop())
+        .unwrap();
+    a.update();
+    assert_eq!(stopped(&mut a), vec![(S1, StopCause::Pop)]);
+}
+
+#[test]
+fn stop_cause_discard_on_push() {
+    let mut a = app::<B>();
+    a.world_mut().spawn((S0, Controller::next(S1)));
+    a.update();
+    a.world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| q.single_mut().try_start(S2))
+        .unwrap()
+        .forget();
+    a.update();
+    assert_eq!(stopped(&mut a), vec![(S1, StopCause::DiscardOnPush)]);
+}
+
+#[test]
+fn stop_cause_reset() {
+    let mut a = app::<B>();
+    a.world_mut().spawn((S0, Controller::next(S1)));
+    a.update();
+    a.world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| q.single_mut().reset())
+        .unwrap();
+    a.update();
+    assert_eq!(stopped(&mut a), vec![(S1, StopCause::Reset)]);
+}
+
+#[test]
+fn stop_cause_pop_into_successor() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((S0, Controller::next(S3))).id();
+    a.update();
+    a.world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| q.single_mut().stop())
+        .unwrap();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), S2);
+    assert_eq!(stopped(&mut a), vec![(S3, StopCause::Pop)]);
+}
@@ -0,0 +1,54 @@
+use std::{fmt::Debug, marker::PhantomData};
+
+use bevy::prelude::*;
+
+use moonshine_behavior::prelude::*;
+
+#[derive(Debug, Reflect)]
+struct Patrol;
+
+#[derive(Debug, Reflect)]
+struct Guard;
+
+#[derive(Component, Debug, Reflect)]
+#[require(Controller<Ai<K>>)]
+struct Ai<K: Debug + Send + Sync + 'static> {
+    active: bool,
+    marker: PhantomData<K>,
+}
+
+impl<K: Debug + Send + Sync + 'static> Ai<K> {
+    fn new(active: bool) -> Self {
+        Self {
+            active,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<K: Debug + Send + Sync + 'static> Behavior for Ai<K> {}
+
+#[test]
+fn generic() {
+    let mut a = App::new();
+    a.add_plugins((
+        MinimalPlugins,
+        BehaviorPlugin::<Ai<Patrol>>::default(),
+        BehaviorPlugin::<Ai<Guard>>::default(),
+    ))
+    .add_systems(Update, (transition::<Ai<Patrol>>, transition::<Ai<Guard>>));
+
+    let e0 = a.world_mut().spawn(Ai::<Patrol>::new(false)).id();
+    let e1 = a.world_mut().spawn(Ai::<Guard>::new(false)).id();
+    a.update();
+
+    a.world_mut()
+        .get_mut::<Controller<Ai<Patrol>>>(e0)
+        .unwrap()
+        .try_start(Ai::new(true))
+        .forget();
+    a.update();
+    assert!(a.world().get::<Ai<Patrol>>(e0).unwrap().active);
+    assert!(!a.world().get::<Ai<Guard>>(e1).unwrap().active);
+    assert!(a.world().get::<Ai<Guard>>(e0).is_none());
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+use moonshine_behavior::{prelude::*, Memory};
+
+mod common;
+
+use common::{app, controller};
+
+#[derive(Component, Default, Debug, Clone, PartialEq, Eq, Reflect)]
+struct S(u8);
+
+impl Behavior for S {}
+
+#[derive(Resource, Default)]
+struct Counts {
+    started: usize,
+    stopped: usize,
+}
+
+fn count(mut started: Started<S>, mut stopped: Stopped<S>, mut counts: ResMut<Counts>) {
+    counts.started += started.read().count();
+    counts.stopped += stopped.read().count();
+}
+
+/// A minimal linear congruential generator, so the test is reproducible without extra dependencies.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self
+            .0
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        self.0 >> 33
+    }
+}
+
+#[test]
+fn random_transitions() {
+    for seed in 0..16 {
+        let mut random = Random(seed);
+        let mut a = app::<S>();
+        a.init_resource::<Counts>()
+            .add_systems(Update, count.after(transition::<S>));
+        let e = a.world_mut().spawn((S(0), Controller::<S>::default())).id();
+        let mut expected = vec![S(0)];
+
+        for _ in 0..200 {
+            match random.next() % 4 {
+                0 | 1 => {
+                    let next = S((random.next() % 8) as u8);
+                    controller::<S>(&mut a, e).try_start(next.clone()).forget();
+                    expected.push(next);
+                }
+                2 => {
+                    controller::<S>(&mut a, e).stop();
+                    if expected.len() > 1 {
+                        expected.pop();
+                    }
+                }
+                _ => {
+                    controller::<S>(&mut a, e).reset();
+                    expected.truncate(1);
+                }
+            }
+            a.update();
+
+            let current = a.world().get::<S>(e).unwrap();
+            let memory = a.world().get::<Memory<S>>(e).unwrap();
+            assert_eq!(Some(current), expected.last(), "seed {seed}");
+            assert_eq!(memory.len() + 1, expected.len(), "seed {seed}");
+            let paused = &expected[..expected.len() - 1];
+            assert!(memory.iter().eq(paused.iter().rev()), "seed {seed}");
+        }
+
+        // Every started behavior is eventually stopped:
+        controller::<S>(&mut a, e).reset();
+        a.update();
+        let counts = a.world().resource::<Counts>();
+        assert_eq!(counts.started, counts.stopped, "seed {seed}");
+    }
+}
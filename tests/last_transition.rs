@@ -0,0 +1,89 @@
+use bevy::prelude::*;
+
+use moonshine_behavior::prelude::*;
+
+mod common;
+
+use common::{app, controller};
+
+#[derive(Component, Default, Debug, PartialEq, Eq, Reflect)]
+enum B {
+    #[default]
+    S0,
+    S1,
+    S2,
+    S3,
+}
+
+use B::*;
+
+impl Behavior for B {
+    fn allows_next(&self, next: &Self) -> bool {
+        *next != S0
+    }
+
+    fn stopped(&self) -> Option<Self> {
+        match self {
+            S2 => Some(S3),
+            _ => None,
+        }
+    }
+}
+
+fn step(a: &mut App, e: Entity, f: impl FnOnce(&mut Controller<B>)) -> TransitionOutcome {
+    f(&mut controller::<B>(a, e));
+    a.update();
+    a.world().get::<LastTransition<B>>(e).unwrap().outcome()
+}
+
+#[test]
+fn last_transition() {
+    use TransitionOutcome as Outcome;
+
+    let mut a = app::<B>();
+    let e = a
+        .world_mut()
+        .spawn((
+            S0,
+            Controller::<B>::default(),
+            LastTransition::<B>::default(),
+        ))
+        .id();
+
+    assert_eq!(step(&mut a, e, |_| {}), Outcome::None);
+    assert_eq!(
+        step(&mut a, e, |c| c.try_start(S1).forget()),
+        Outcome::Started(1)
+    );
+    assert_eq!(step(&mut a, e, |_| {}), Outcome::None);
+    assert_eq!(
+        step(&mut a, e, |c| c.try_start(S2).forget()),
+        Outcome::Started(2)
+    );
+
+    // S2 is paused, and its successor is started:
+    assert_eq!(step(&mut a, e, |c| c.stop()), Outcome::Replaced(3));
+    assert_eq!(
+        step(&mut a, e, |c| c.stop()),
+        Outcome::Stopped { from: 3, to: 2 }
+    );
+    assert_eq!(
+        step(&mut a, e, |c| c.stop_n(2)),
+        Outcome::Stopped { from: 2, to: 0 }
+    );
+
+    assert_eq!(
+        step(&mut a, e, |c| c.try_start(S1).forget()),
+        Outcome::Started(1)
+    );
+    assert_eq!(step(&mut a, e, |c| c.reset()), Outcome::Reset { from: 1 });
+
+    // Redundant reset, failed pop, and a transition which is not allowed:
+    assert_eq!(step(&mut a, e, |c| c.reset()), Outcome::Rejected);
+    assert_eq!(step(&mut a, e, |c| c.stop()), Outcome::Rejected);
+    assert_eq!(
+        step(&mut a, e, |c| c.try_start(S0).forget()),
+        Outcome::Rejected
+    );
+    assert_eq!(step(&mut a, e, |_| {}), Outcome::None);
+}
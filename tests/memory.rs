@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+
+use moonshine_behavior::{prelude::*, Memory};
+
+mod common;
+
+use common::{app, start};
+
+#[derive(Component, Default, Debug, PartialEq, Eq, Reflect)]
+enum B {
+    #[default]
+    Idle,
+    Sing,
+    Dance,
+}
+
+use B::*;
+
+impl Behavior for B {
+    fn allows_next(&self, next: &Self) -> bool {
+        self != next
+    }
+
+    fn allows_next_in_memory(&self, next: &Self, memory: &Memory<Self>) -> bool {
+        self.allows_next(next) && !memory.contains(next)
+    }
+}
+
+#[test]
+fn allows_next_in_memory() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((Idle, Controller::<B>::default())).id();
+    assert!(start(&mut a, e, Sing).is_ok());
+    assert!(start(&mut a, e, Dance).is_ok());
+
+    // Dance -> Sing is allowed, but Sing is already paused:
+    assert!(Dance.allows_next(&Sing));
+    assert!(start(&mut a, e, Sing).is_err());
+    assert_eq!(*a.world().get::<B>(e).unwrap(), Dance);
+    assert_eq!(a.world().get::<Memory<B>>(e).unwrap().len(), 2);
+}
+
+#[test]
+fn previous() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((Idle, Controller::<B>::default())).id();
+    assert!(start(&mut a, e, Sing).is_ok());
+    assert!(start(&mut a, e, Dance).is_ok());
+
+    let world = a.world_mut();
+    let previous = world.query::<Previous<B>>().get(world, e).unwrap();
+    assert_eq!(previous.get(), Some(&Sing));
+    assert_eq!(previous.depth(), 2);
+    assert!(previous.memory().contains(&Idle));
+}
+
+#[test]
+fn as_slice() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((Idle, Controller::<B>::default())).id();
+    assert!(start(&mut a, e, Sing).is_ok());
+    assert!(start(&mut a, e, Dance).is_ok());
+
+    let memory = a.world().get::<Memory<B>>(e).unwrap();
+    assert_eq!(memory.as_slice(), &[Idle, Sing]);
+    assert!(memory.iter().eq(memory.as_slice().iter().rev()));
+}
@@ -0,0 +1,84 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+use moonshine_behavior::{add_behaviors, prelude::*, reset_all};
+
+#[derive(Component, Default, Debug, PartialEq, Eq, Reflect)]
+enum A {
+    #[default]
+    A0,
+    A1,
+}
+
+impl Behavior for A {}
+
+#[derive(Component, Default, Debug, PartialEq, Eq, Reflect)]
+enum B {
+    #[default]
+    B0,
+    B1,
+}
+
+impl Behavior for B {}
+
+fn start_b(mut events: Started<A>, mut query: Query<&mut Controller<B>>) {
+    for event in events.read() {
+        query
+            .get_mut(event.entity())
+            .unwrap()
+            .try_start(B::B1)
+            .forget();
+    }
+}
+
+#[test]
+fn ordered() {
+    let mut a = App::new();
+    a.add_plugins((
+        MinimalPlugins,
+        BehaviorPlugin::<A>::default(),
+        BehaviorPlugin::<B>::default(),
+    ))
+    .add_systems(Update, (transition::<A>, start_b, transition::<B>).chain());
+
+    let e = a
+        .world_mut()
+        .spawn((
+            A::A0,
+            Controller::next(A::A1),
+            B::B0,
+            Controller::<B>::default(),
+        ))
+        .id();
+    a.update();
+    assert_eq!(*a.world().get::<A>(e).unwrap(), A::A1);
+    assert_eq!(*a.world().get::<B>(e).unwrap(), B::B1);
+}
+
+#[test]
+fn add_and_reset_all() {
+    let mut a = App::new();
+    a.add_plugins(MinimalPlugins);
+    add_behaviors!(&mut a, Update, A, B);
+
+    let e = a
+        .world_mut()
+        .spawn((
+            A::A0,
+            Controller::next(A::A1),
+            B::B0,
+            Controller::next(B::B1),
+        ))
+        .id();
+    a.update();
+    assert_eq!(*a.world().get::<A>(e).unwrap(), A::A1);
+    assert_eq!(*a.world().get::<B>(e).unwrap(), B::B1);
+
+    a.world_mut()
+        .run_system_once(move |mut commands: Commands| {
+            commands.entity(e).queue(reset_all::<(A, B)>);
+        })
+        .unwrap();
+    a.update();
+    assert_eq!(*a.world().get::<A>(e).unwrap(), A::A0);
+    assert_eq!(*a.world().get::<B>(e).unwrap(), B::B0);
+}
@@ -0,0 +1,58 @@
+use bevy::{ecs::system::RunSystemOnce, prelude::*};
+
+use moonshine_behavior::prelude::*;
+
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+struct B(u8);
+
+impl Behavior for B {}
+
+fn app(quiet_reset: bool) -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        BehaviorPlugin::<B> {
+            quiet_reset,
+            ..default()
+        },
+    ))
+    .add_systems(Update, transition::<B>);
+    app
+}
+
+/// Resets an entity with 3 paused behaviors and returns the number of stopped and reset events.
+fn reset(quiet_reset: bool) -> (usize, Vec<usize>) {
+    let mut a = app(quiet_reset);
+    let e = a.world_mut().spawn((B(0), Controller::<B>::default())).id();
+    for i in 1..=3 {
+        a.world_mut()
+            .get_mut::<Controller<B>>(e)
+            .unwrap()
+            .try_start(B(i))
+            .forget();
+        a.update();
+    }
+
+    a.world_mut().get_mut::<Controller<B>>(e).unwrap().reset();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), B(0));
+
+    a.world_mut()
+        .run_system_once(
+            |mut stopped: Stopped<B>, mut reset: Reset<B>| -> (usize, Vec<usize>) {
+                let reset = reset.read().map(|event| event.count).collect();
+                (stopped.read().count(), reset)
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn reset_events() {
+    assert_eq!(reset(false), (3, vec![3]));
+}
+
+#[test]
+fn quiet_reset_events() {
+    assert_eq!(reset(true), (0, vec![3]));
+}
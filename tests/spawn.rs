@@ -0,0 +1,36 @@
+use bevy::prelude::*;
+
+use moonshine_behavior::prelude::*;
+
+mod common;
+
+use common::app;
+
+// This behavior intentionally does not implement `Default`:
+#[derive(Component, Debug, PartialEq, Eq, Reflect)]
+#[require(Controller<B>)]
+#[reflect(Component)]
+enum B {
+    S0(u32),
+    S1(u32),
+}
+
+impl Behavior for B {}
+
+#[test]
+fn without_default() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn(B::S0(1)).id();
+    a.update();
+    a.world_mut()
+        .get_mut::<Controller<B>>(e)
+        .unwrap()
+        .try_start(B::S1(2))
+        .forget();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), B::S1(2));
+
+    a.world_mut().get_mut::<Controller<B>>(e).unwrap().stop();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), B::S0(1));
+}
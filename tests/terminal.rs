@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+
+use moonshine_behavior::prelude::*;
+
+mod common;
+
+use common::{app, controller};
+
+#[derive(Component, Default, Debug, PartialEq, Eq, Reflect)]
+enum B {
+    #[default]
+    Idle,
+    Walk,
+    Dead,
+}
+
+use B::*;
+
+impl Behavior for B {
+    fn is_terminal(&self) -> bool {
+        matches!(self, Dead)
+    }
+}
+
+#[test]
+fn terminal() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((Idle, Controller::next(Walk))).id();
+    a.update();
+    controller::<B>(&mut a, e).try_start(Dead).forget();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), Dead);
+
+    let r = controller::<B>(&mut a, e).try_start(Walk);
+    a.update();
+    assert!(r.poll().unwrap().is_err());
+    assert_eq!(*a.world().get::<B>(e).unwrap(), Dead);
+
+    controller::<B>(&mut a, e).stop();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), Dead);
+
+    controller::<B>(&mut a, e).reset();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), Idle);
+}
@@ -1,8 +1,22 @@
-use bevy::{ecs::system::RunSystemOnce, prelude::*};
+use std::{
+    io,
+    sync::{Arc, Mutex},
+};
 
-use moonshine_behavior::prelude::*;
+use bevy::{
+    ecs::{query::QueryFilter, system::RunSystemOnce},
+    log::tracing_subscriber::{self, fmt::MakeWriter},
+    prelude::*,
+    utils::tracing,
+};
 
-#[derive(Component, Default, Debug, PartialEq, Eq, Reflect)]
+use moonshine_behavior::{prelude::*, Memory};
+
+mod common;
+
+use common::app;
+
+#[derive(Component, Default, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
 enum B {
     #[default]
     S0,
@@ -26,16 +40,9 @@ impl Behavior for B {
     }
 }
 
-fn app() -> App {
-    let mut app = App::new();
-    app.add_plugins((MinimalPlugins, BehaviorPlugin::<B>::default()))
-        .add_systems(Update, transition::<B>);
-    app
-}
-
 #[test]
 fn initial() {
-    let mut a = app();
+    let mut a = app::<B>();
     let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
     assert_eq!(*a.world().get::<B>(e).unwrap(), S0);
     assert!(a
@@ -52,7 +59,7 @@ fn initial() {
 
 #[test]
 fn push() {
-    let mut a = app();
+    let mut a = app::<B>();
     let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
     let r = a
         .world_mut()
@@ -73,9 +80,27 @@ fn push() {
         .unwrap());
 }
 
+#[test]
+fn push_on_spawn() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((S0, Controller::next(S1))).id();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), S1);
+    assert_eq!(a.world().resource::<Events<PausedEvent<B>>>().len(), 1);
+    assert_eq!(a.world().resource::<Events<StartedEvent<B>>>().len(), 1);
+    assert_eq!(a.world().resource::<Events<StoppedEvent<B>>>().len(), 0);
+    assert_eq!(a.world().get::<Memory<B>>(e).unwrap().as_slice(), &[S0]);
+
+    // The transition requested on spawn is complete:
+    let controller = a.world().get::<Controller<B>>(e).unwrap();
+    assert!(controller.is_started());
+    assert!(!controller.is_suspending());
+    assert!(!controller.is_transitioning_to(|_| true));
+}
+
 #[test]
 fn push_fail() {
-    let mut a = app();
+    let mut a = app::<B>();
     let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
     let r = a
         .world_mut()
@@ -90,9 +115,27 @@ fn push_fail() {
         .unwrap());
 }
 
+#[test]
+fn push_fail_error() {
+    fn try_push(a: &mut App) -> Result<(), Box<dyn std::error::Error>> {
+        let r = a
+            .world_mut()
+            .run_system_once(|mut q: Query<&mut Controller<B>>| q.single_mut().try_start(S2))
+            .unwrap();
+        a.update();
+        r.poll().unwrap()?;
+        Ok(())
+    }
+
+    let mut a = app::<B>();
+    a.world_mut().spawn((S0, Controller::<B>::default()));
+    let error = try_push(&mut a).unwrap_err();
+    assert_eq!(error.to_string(), "transition to S2 is not allowed");
+}
+
 #[test]
 fn pop() {
-    let mut a = app();
+    let mut a = app::<B>();
     let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
     let _ = a
         .world_mut()
@@ -119,7 +162,7 @@ fn pop() {
 
 #[test]
 fn reset() {
-    let mut a = app();
+    let mut a = app::<B>();
     let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
     let _ = a
         .world_mut()
@@ -150,7 +193,7 @@ fn reset() {
 
 #[test]
 fn chain() {
-    let mut a = app();
+    let mut a = app::<B>();
     let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
     let _ = a
         .world_mut()
@@ -194,3 +237,385 @@ fn chain() {
         .run_system_once(|q: Query<&Controller<B>>| { q.single().is_resumed() })
         .unwrap());
 }
+
+#[test]
+fn reset_stopped_events() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
+    let _ = a
+        .world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| q.single_mut().try_start(S1));
+    a.update();
+    let _ = a
+        .world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| q.single_mut().try_start(S2));
+    a.update();
+    a.world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| {
+            q.single_mut().reset();
+        })
+        .unwrap();
+    a.update();
+
+    // Each stopped event observes the stack after the whole reset:
+    let observed = a
+        .world_mut()
+        .run_system_once(
+            |mut events: Stopped<B>, q: Query<(&B, &Memory<B>)>| -> Vec<(B, B, usize)> {
+                events
+                    .read()
+                    .map(|event| {
+                        let (current, memory) = q.get(event.entity()).unwrap();
+                        (event.behavior, *current, memory.len())
+                    })
+                    .collect()
+            },
+        )
+        .unwrap();
+    assert_eq!(observed, vec![(S1, S0, 0), (S2, S0, 0)]);
+    assert_eq!(*a.world().get::<B>(e).unwrap(), S0);
+}
+
+fn stack(a: &App, e: Entity) -> (B, Vec<B>) {
+    let current = *a.world().get::<B>(e).unwrap();
+    let memory = a.world().get::<Memory<B>>(e).unwrap();
+    (current, memory.as_slice().to_vec())
+}
+
+fn stop_n(n: usize) -> (App, Entity) {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((S0, Controller::next(S1))).id();
+    a.update();
+    a.world_mut()
+        .get_mut::<Controller<B>>(e)
+        .unwrap()
+        .try_start(S2)
+        .forget();
+    a.update();
+    a.world_mut().get_mut::<Controller<B>>(e).unwrap().stop_n(n);
+    a.update();
+    (a, e)
+}
+
+fn stopped_and_resumed(a: &mut App) -> (Vec<B>, usize) {
+    a.world_mut()
+        .run_system_once(
+            |mut stopped: Stopped<B>, mut resumed: Resumed<B>| -> (Vec<B>, usize) {
+                let stopped = stopped
+                    .read()
+                    .inspect(|event| assert_eq!(event.cause, StopCause::Pop))
+                    .map(|event| event.behavior)
+                    .collect();
+                (stopped, resumed.read().count())
+            },
+        )
+        .unwrap()
+}
+
+#[test]
+fn stop_n_within_depth() {
+    let (mut a, e) = stop_n(1);
+    assert_eq!(stack(&a, e), (S1, vec![S0]));
+    assert_eq!(stopped_and_resumed(&mut a), (vec![S2], 1));
+
+    let (mut a, e) = stop_n(2);
+    // Successor of S2 (S3) is not started:
+    assert_eq!(stack(&a, e), (S0, vec![]));
+    assert_eq!(stopped_and_resumed(&mut a), (vec![S1, S2], 1));
+    assert!(a.world().get::<Controller<B>>(e).unwrap().is_resumed());
+}
+
+#[test]
+fn stop_n_exceeds_depth() {
+    let (mut a, e) = stop_n(5);
+    assert_eq!(stack(&a, e), (S0, vec![]));
+    assert_eq!(stopped_and_resumed(&mut a), (vec![S1, S2], 1));
+}
+
+#[test]
+fn stop_n_zero() {
+    let (mut a, e) = stop_n(0);
+    assert_eq!(stack(&a, e), (S2, vec![S0, S1]));
+    assert_eq!(stopped_and_resumed(&mut a), (vec![], 0));
+    assert!(a.world().get::<Controller<B>>(e).unwrap().is_stable());
+}
+
+#[test]
+fn event_order() {
+    let mut a = app::<B>();
+    let e0 = a.world_mut().spawn((S0, Controller::next(S1))).id();
+    let e1 = a.world_mut().spawn((S0, Controller::next(S1))).id();
+    a.update();
+    a.world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| {
+            q.iter_mut().for_each(|mut c| c.try_start(S2).forget())
+        })
+        .unwrap();
+    a.update();
+    a.world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| {
+            q.iter_mut().for_each(|mut c| c.reset())
+        })
+        .unwrap();
+    a.update();
+
+    let stopped = a
+        .world_mut()
+        .run_system_once(|mut events: Stopped<B>| -> Vec<(Entity, B)> {
+            events
+                .read()
+                .map(|event| (event.entity(), event.behavior))
+                .collect()
+        })
+        .unwrap();
+
+    // Events of each entity are sent together, in step order:
+    assert_eq!(stopped.len(), 4);
+    for chunk in stopped.chunks(2) {
+        assert_eq!(chunk[0].0, chunk[1].0);
+        assert_eq!([chunk[0].1, chunk[1].1], [S1, S2]);
+    }
+    assert_ne!(stopped[0].0, stopped[2].0);
+    for e in [e0, e1] {
+        assert!(stopped.iter().any(|(entity, _)| *entity == e));
+    }
+}
+
+#[test]
+fn run_transitions_on_world() {
+    let mut w = World::new();
+    let e = w.spawn((S0, Controller::<B>::default())).id();
+    run_transitions::<B>(&mut w);
+    w.get_mut::<Controller<B>>(e)
+        .unwrap()
+        .try_start(S1)
+        .forget();
+    run_transitions::<B>(&mut w);
+    assert_eq!(*w.get::<B>(e).unwrap(), S1);
+    assert!(w.get::<Controller<B>>(e).unwrap().is_started());
+
+    w.get_mut::<Controller<B>>(e).unwrap().stop();
+    run_transitions::<B>(&mut w);
+    assert_eq!(*w.get::<B>(e).unwrap(), S0);
+    assert!(w.get::<Controller<B>>(e).unwrap().is_resumed());
+
+    run_transitions::<B>(&mut w);
+    assert!(w.get::<Controller<B>>(e).unwrap().is_stable());
+}
+
+#[test]
+fn without_plugin() {
+    // Transitions still work without the plugin; only a warning is logged and no events are sent.
+    let mut w = World::new();
+    let e = w.spawn((S0, Controller::next(S1))).id();
+    let log = capture_log(|| {
+        run_transitions::<B>(&mut w);
+        run_transitions::<B>(&mut w);
+    });
+    assert_eq!(*w.get::<B>(e).unwrap(), S1);
+    assert!(!w.contains_resource::<Events<StartedEvent<B>>>());
+    assert_eq!(
+        log.matches("did you forget to add BehaviorPlugin").count(),
+        1
+    );
+}
+
+#[test]
+fn with_events_plugin() {
+    // Behaviors which do not support reflection are registered with `behavior_events_plugin`.
+    let mut a = App::new();
+    #[allow(deprecated)]
+    moonshine_behavior::behavior_events_plugin::<B>(&mut a);
+    let e = a.world_mut().spawn((S0, Controller::next(S1))).id();
+    let log = capture_log(|| run_transitions::<B>(a.world_mut()));
+    assert_eq!(*a.world().get::<B>(e).unwrap(), S1);
+    assert!(!log.contains("did you forget to add BehaviorPlugin"));
+}
+
+#[test]
+fn transitions_disabled() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
+    a.world_mut()
+        .resource_mut::<TransitionsEnabled<B>>()
+        .disable();
+    assert!(a.world().get::<Controller<B>>(e).unwrap().is_activated());
+    a.update();
+    assert!(!a.world().get::<Controller<B>>(e).unwrap().is_activated());
+
+    a.world_mut()
+        .run_system_once(|mut q: Query<&mut Controller<B>>| q.single_mut().try_start(S1))
+        .unwrap()
+        .forget();
+    a.update();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), S0);
+    assert!(!a.world().get::<Controller<B>>(e).unwrap().is_activated());
+    assert!(a.world().get::<Controller<B>>(e).unwrap().is_suspending());
+
+    a.world_mut()
+        .resource_mut::<TransitionsEnabled<B>>()
+        .enable();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), S1);
+    assert!(a.world().get::<Controller<B>>(e).unwrap().is_started());
+}
+
+#[test]
+fn transition_requests() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
+    a.world_mut()
+        .resource_mut::<TransitionRequests<B>>()
+        .push(e, TransitionRequest::Start(S1));
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), S1);
+    assert!(a.world().resource::<TransitionRequests<B>>().is_empty());
+
+    a.world_mut()
+        .resource_mut::<TransitionRequests<B>>()
+        .push(e, TransitionRequest::Stop);
+    a.update();
+    assert_eq!(*a.world().get::<B>(e).unwrap(), S0);
+}
+
+#[test]
+fn transition_requests_unchanged() {
+    let mut a = app::<B>();
+    a.world_mut().spawn((S0, Controller::<B>::default()));
+    a.update();
+    let tick = a.world().read_change_tick();
+    a.update();
+
+    // Requests are not marked as changed if there are none:
+    let requests = a.world().resource_ref::<TransitionRequests<B>>();
+    assert!(!requests
+        .last_changed()
+        .is_newer_than(tick, a.world().read_change_tick()));
+}
+
+#[test]
+fn transition_metrics() {
+    let mut a = app::<B>();
+    a.init_resource::<TransitionMetrics<B>>();
+    let e0 = a.world_mut().spawn((S0, Controller::next(S1))).id();
+    let e1 = a.world_mut().spawn((S0, Controller::next(S2))).id();
+    a.update();
+    assert_eq!(*a.world().get::<B>(e0).unwrap(), S1);
+    assert_eq!(*a.world().get::<B>(e1).unwrap(), S0);
+
+    let metrics = a.world().resource::<TransitionMetrics<B>>();
+    assert_eq!(metrics.pushes, 1);
+    assert_eq!(metrics.rejections, 1);
+    assert_eq!(metrics.total(), 2);
+
+    a.world_mut().resource_mut::<TransitionMetrics<B>>().clear();
+    a.world_mut().get_mut::<Controller<B>>(e0).unwrap().stop();
+    a.world_mut().get_mut::<Controller<B>>(e1).unwrap().reset();
+    a.update();
+
+    let metrics = a.world().resource::<TransitionMetrics<B>>();
+    assert_eq!(metrics.pops, 1);
+    assert_eq!(metrics.resets, 0);
+    assert_eq!(metrics.rejections, 1); // Reset of the initial behavior is redundant
+    assert_eq!(metrics.total(), 2);
+}
+
+#[test]
+fn is_transitioning_to() {
+    let mut a = app::<B>();
+    let e = a.world_mut().spawn((S0, Controller::<B>::default())).id();
+    let mut controller = a.world_mut().get_mut::<Controller<B>>(e).unwrap();
+    controller.try_start(S1).forget();
+    assert!(controller.is_transitioning_to(|next| *next == S1));
+    assert!(!controller.is_transitioning_to(|next| *next == S2));
+
+    a.update();
+    let controller = a.world().get::<Controller<B>>(e).unwrap();
+    assert!(!controller.is_transitioning_to(|next| *next == S1));
+}
+
+#[test]
+fn transition_requests_many() {
+    let mut a = app::<B>();
+    let entities: Vec<Entity> = (0..3)
+        .map(|_| a.world_mut().spawn((S0, Controller::<B>::default())).id())
+        .collect();
+    a.world_mut()
+        .resource_mut::<TransitionRequests<B>>()
+        .push_many(entities.iter().copied(), TransitionRequest::Start(S1));
+    a.update();
+    for entity in entities {
+        assert_eq!(*a.world().get::<B>(entity).unwrap(), S1);
+    }
+}
+
+#[test]
+fn request_timing() {
+    #[derive(Component)]
+    struct Late;
+
+    fn request<F: QueryFilter + 'static>(mut q: Query<(&B, &mut Controller<B>), F>) {
+        for (behavior, mut controller) in &mut q {
+            if *behavior == S0 && !controller.is_suspending() {
+                controller.try_start(S1).forget();
+            }
+        }
+    }
+
+    let mut a = App::new();
+    a.add_plugins((MinimalPlugins, BehaviorPlugin::<B>::default()))
+        .add_systems(
+            Update,
+            (
+                request::<Without<Late>>,
+                transition::<B>,
+                request::<With<Late>>,
+            )
+                .chain(),
+        );
+    let early = a.world_mut().spawn((S0, Controller::<B>::default())).id();
+    let late = a
+        .world_mut()
+        .spawn((S0, Controller::<B>::default(), Late))
+        .id();
+
+    // Requested before `transition` runs: invoked within the same update.
+    // Requested after `transition` has run: invoked in the next update.
+    a.update();
+    assert_eq!(*a.world().get::<B>(early).unwrap(), S1);
+    assert_eq!(*a.world().get::<B>(late).unwrap(), S0);
+    a.update();
+    assert_eq!(*a.world().get::<B>(late).unwrap(), S1);
+}
+
+#[derive(Clone, Default)]
+struct Log(Arc<Mutex<Vec<u8>>>);
+
+impl io::Write for Log {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<'a> MakeWriter<'a> for Log {
+    type Writer = Log;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        self.clone()
+    }
+}
+
+/// Returns everything logged on this thread while running `f`.
+fn capture_log(f: impl FnOnce()) -> String {
+    let log = Log::default();
+    let subscriber = tracing_subscriber::fmt().with_writer(log.clone()).finish();
+    tracing::subscriber::with_default(subscriber, f);
+    let bytes = log.0.lock().unwrap().clone();
+    String::from_utf8(bytes).unwrap()
+}
@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+
+use moonshine_behavior::{prelude::*, validate_transitions, TransitionReport};
+
+#[derive(Component, Debug)]
+enum B {
+    S0,
+    S1,
+    S2,
+    S3,
+}
+
+use B::*;
+
+impl Behavior for B {
+    fn allows_next(&self, next: &Self) -> bool {
+        // S3 is never reachable:
+        matches!((self, next), (S0, S1) | (S1, S2) | (S3, S0))
+    }
+}
+
+#[test]
+fn report() {
+    let report = validate_transitions(&[S0, S1, S2, S3]);
+    assert!(!report.is_valid());
+    assert_eq!(
+        report,
+        TransitionReport {
+            unreachable: vec![3],
+            sinks: vec![2],
+        }
+    );
+}
+
+#[test]
+fn valid() {
+    let report = validate_transitions(&[S0, S1, S2]);
+    assert!(report.is_valid());
+    assert_eq!(report.sinks, vec![2]);
+}
+
+#[derive(Component, Debug)]
+enum T {
+    Idle,
+    Dead,
+    Ghost,
+}
+
+impl Behavior for T {
+    fn allows_next(&self, next: &Self) -> bool {
+        // Ghost is only reachable through Dead, which is terminal:
+        use T::*;
+        matches!((self, next), (Idle, Dead) | (Dead, Ghost))
+    }
+
+    fn is_terminal(&self) -> bool {
+        matches!(self, T::Dead)
+    }
+}
+
+#[test]
+fn terminal() {
+    let report = validate_transitions(&[T::Idle, T::Dead, T::Ghost]);
+    assert!(!report.is_valid());
+    assert_eq!(
+        report,
+        TransitionReport {
+            unreachable: vec![2],
+            sinks: vec![1, 2],
+        }
+    );
+}